@@ -1,17 +1,81 @@
+use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::Result;
-use clap::{ command, Parser, Subcommand };
+use clap::{ command, Parser, Subcommand, ValueEnum };
 use sdk::token_utils::{
-    derive_metadata_pda,
-    fetch_all_token2022_mints,
-    fetch_metadata_accounts,
+    build_extension_records,
+    build_metadata_records,
+    fetch_mint_accounts,
+    fetch_mint_accounts_cached,
+    fetch_token_info,
     filter_mints_with_extensions,
     print_metadata_results,
     print_mints_with_extensions,
+    print_token_info,
+    resolve_mint_metadata,
+    TokenProgram,
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
+use spl_token_2022::extension::ExtensionType;
+
+/// Output format for command results.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text, printed as the commands have always done.
+    Text,
+    /// Structured JSON records, one per mint, suitable for piping into other tools.
+    Json,
+}
+
+/// Which token program(s) to query mints from.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ProgramSelector {
+    /// The original SPL Token program only.
+    Legacy,
+    /// The Token-2022 program only.
+    Token2022,
+    /// Both the original SPL Token program and Token-2022.
+    All,
+}
+
+impl ProgramSelector {
+    fn programs(self) -> Vec<TokenProgram> {
+        match self {
+            ProgramSelector::Legacy => vec![TokenProgram::Legacy],
+            ProgramSelector::Token2022 => vec![TokenProgram::Token2022],
+            ProgramSelector::All => vec![TokenProgram::Legacy, TokenProgram::Token2022],
+        }
+    }
+}
+
+/// A Token-2022 extension to filter mints by, pushed down into the RPC
+/// program-account scan as a server-side `Memcmp` filter.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExtensionFilter {
+    TransferFeeConfig,
+    InterestBearingConfig,
+    MintCloseAuthority,
+    PermanentDelegate,
+    TransferHook,
+    MetadataPointer,
+    DefaultAccountState,
+}
+
+impl ExtensionFilter {
+    fn extension_type(self) -> ExtensionType {
+        match self {
+            ExtensionFilter::TransferFeeConfig => ExtensionType::TransferFeeConfig,
+            ExtensionFilter::InterestBearingConfig => ExtensionType::InterestBearingConfig,
+            ExtensionFilter::MintCloseAuthority => ExtensionType::MintCloseAuthority,
+            ExtensionFilter::PermanentDelegate => ExtensionType::PermanentDelegate,
+            ExtensionFilter::TransferHook => ExtensionType::TransferHook,
+            ExtensionFilter::MetadataPointer => ExtensionType::MetadataPointer,
+            ExtensionFilter::DefaultAccountState => ExtensionType::DefaultAccountState,
+        }
+    }
+}
 
 /// Entry point for the CLI app, using the `clap` derive macro to auto-generate argument parsing.
 #[derive(Parser)]
@@ -32,6 +96,52 @@ pub struct Cli {
     )]
     pub rpc_url: String,
 
+    /// Output format for command results
+    #[arg(
+        long,
+        value_enum,
+        help = "Output format: human-readable text or structured JSON",
+        default_value = "text",
+        global = true
+    )]
+    pub format: OutputFormat,
+
+    /// Which token program(s) to query mints from
+    #[arg(
+        long,
+        value_enum,
+        help = "Which token program(s) to fetch mints from",
+        default_value = "token2022",
+        global = true
+    )]
+    pub program: ProgramSelector,
+
+    /// Path to a local zstd-compressed cache of fetched mint accounts
+    #[arg(
+        long,
+        help = "Path to a local cache file of fetched mint accounts, to avoid repeated program scans",
+        global = true
+    )]
+    pub cache: Option<PathBuf>,
+
+    /// Maximum age, in seconds, of the cache before it's considered stale
+    #[arg(
+        long,
+        help = "Maximum age in seconds of the --cache file before it's refreshed",
+        default_value_t = 300,
+        global = true
+    )]
+    pub max_age: u64,
+
+    /// Only fetch mints carrying this Token-2022 extension
+    #[arg(
+        long,
+        value_enum,
+        help = "Only fetch mints carrying this Token-2022 extension, filtered server-side",
+        global = true
+    )]
+    pub extension: Option<ExtensionFilter>,
+
     /// The subcommand to run
     #[command(subcommand)]
     pub command: Commands,
@@ -47,6 +157,13 @@ pub enum Commands {
     /// Retrieve all SPL Token-2022 mints that use token extensions
     #[command(about = "Fetch all SPL Token-2022 mints with extensions and print those extensions")]
     GetTokensWithExtensions,
+
+    /// Retrieve total supply and largest holder accounts for a single Token-2022 mint
+    #[command(about = "Fetch the total supply and largest holder accounts for a Token-2022 mint")]
+    GetTokenInfo {
+        /// The Token-2022 mint to query
+        mint: Pubkey,
+    },
 }
 
 #[tokio::main]
@@ -56,44 +173,94 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     // Create the Solana RPC client using user-specified or default endpoint
+    let rpc_url = cli.rpc_url.clone();
     let rpc = RpcClient::new_with_timeout(cli.rpc_url, Duration::from_secs(600));
 
+    // Which token program(s) to fetch mints from, per the `--program` flag
+    let programs = cli.program.programs();
+    let max_age = Duration::from_secs(cli.max_age);
+    let extension = cli.extension.map(ExtensionFilter::extension_type);
+
+    if extension.is_some() {
+        eprintln!(
+            "Warning: --extension only matches mints whose *first* TLV extension is the one \
+             requested. Mints that carry it as a later extension are silently excluded; re-run \
+             without --extension if you need a complete result."
+        );
+    }
+
     //Dispatch based on the subcommand provided by the user
     match cli.command {
         Commands::GetTokensWithMetadataAccount => {
-            // Fetch all token-2022 mint accounts
-            let mint_accounts = fetch_all_token2022_mints(&rpc).await?;
-
-            println!(
-                "Fetched all the accounts. Number of accounts fetched: {}",
-                mint_accounts.len()
-            );
-
-            // For each mint account, derive the corresponding Metadata PDA using Metaplex
-            let metadata_pubkeys: Vec<Pubkey> = mint_accounts
-                .iter()
-                .map(|(mint_pubkey, _)| derive_metadata_pda(mint_pubkey))
-                .collect();
+            // Fetch all mint accounts for the selected program(s). An
+            // `--extension` filter is pushed server-side and bypasses the
+            // cache, which only ever stores unfiltered full scans.
+            let mint_accounts = if extension.is_some() {
+                fetch_mint_accounts(&rpc, &programs, extension).await?
+            } else {
+                match &cli.cache {
+                    Some(cache_path) =>
+                        fetch_mint_accounts_cached(&rpc, &rpc_url, &programs, cache_path, max_age).await?,
+                    None => fetch_mint_accounts(&rpc, &programs, None).await?,
+                }
+            };
 
-            // Fetch account data for each derived Metadata PDA (many will be empty or missing)
-            let metadata_accounts = fetch_metadata_accounts(&rpc, &metadata_pubkeys).await?;
+            // Resolve each mint's metadata: via its `MetadataPointer` extension
+            // (inline or pointing at an external account) when present, falling
+            // back to the Metaplex PDA otherwise.
+            let resolved = resolve_mint_metadata(&rpc, &mint_accounts).await?;
+            let records = build_metadata_records(&mint_accounts, resolved);
 
-            // Print mint + metadata account addresses for those metadata accounts that exist and can be deserialized
-            print_metadata_results(&metadata_pubkeys, &metadata_accounts);
+            match cli.format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&records)?),
+                OutputFormat::Text => {
+                    println!(
+                        "Fetched all the accounts. Number of accounts fetched: {}",
+                        mint_accounts.len()
+                    );
+                    print_metadata_results(&records);
+                }
+            }
         }
-        // Command 2: Get all token-2022 mints that have one or more token extensions
+        // Command 2: Get all mints that have one or more token extensions
         Commands::GetTokensWithExtensions => {
-            // Fetch all Token-2022 mint accounts
-            let accounts = fetch_all_token2022_mints(&rpc).await?;
+            // Fetch all mint accounts for the selected program(s). An
+            // `--extension` filter is pushed server-side and bypasses the
+            // cache, which only ever stores unfiltered full scans.
+            let accounts = if extension.is_some() {
+                fetch_mint_accounts(&rpc, &programs, extension).await?
+            } else {
+                match &cli.cache {
+                    Some(cache_path) =>
+                        fetch_mint_accounts_cached(&rpc, &rpc_url, &programs, cache_path, max_age).await?,
+                    None => fetch_mint_accounts(&rpc, &programs, None).await?,
+                }
+            };
 
             // Filter the mint accounts to only those that include one or more TLV-based token extensions
             let mints_with_exts = filter_mints_with_extensions(&accounts);
 
-            // Print the mint addresses and their associated extension names
-            if mints_with_exts.is_empty() {
-                println!("No mint accounts with token extensions found.");
-            } else {
-                print_mints_with_extensions(&mints_with_exts);
+            // Build structured records for the matching mints
+            let records = build_extension_records(&accounts, &mints_with_exts);
+
+            match cli.format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&records)?),
+                OutputFormat::Text => {
+                    if records.is_empty() {
+                        println!("No mint accounts with token extensions found.");
+                    } else {
+                        print_mints_with_extensions(&records);
+                    }
+                }
+            }
+        }
+        // Command 3: Get total supply and largest holders for a single mint
+        Commands::GetTokenInfo { mint } => {
+            let info = fetch_token_info(&rpc, &mint).await?;
+
+            match cli.format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&info)?),
+                OutputFormat::Text => print_token_info(&info),
             }
         }
     }
@@ -1,36 +1,165 @@
+use std::path::Path;
+use std::time::{ Duration, SystemTime, UNIX_EPOCH };
+
 use futures::future::join_all;
 use mpl_token_metadata::accounts::Metadata;
+use serde::{ Deserialize, Serialize };
 use solana_account_decoder::UiAccountEncoding;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_client::rpc_config::{ RpcAccountInfoConfig, RpcProgramAccountsConfig };
 use solana_client::rpc_filter::{ Memcmp, MemcmpEncodedBytes, RpcFilterType };
 use solana_sdk::account::Account;
 use solana_sdk::commitment_config::CommitmentConfig;
-use solana_sdk::pubkey;
+use solana_sdk::program_pack::Pack;
 use solana_sdk::pubkey::Pubkey;
 use spl_token_2022::extension::StateWithExtensions;
 use spl_token_2022::{ extension::BaseStateWithExtensions, state::Mint };
+use spl_token_2022::{ amount_to_ui_amount, amount_to_ui_amount_string_trimmed };
+use spl_token_2022::extension::ExtensionType;
+use spl_token_2022::extension::default_account_state::DefaultAccountState;
+use spl_token_2022::extension::interest_bearing_mint::InterestBearingConfig;
+use spl_token_2022::extension::metadata_pointer::MetadataPointer;
+use spl_token_2022::extension::mint_close_authority::MintCloseAuthority;
+use spl_token_2022::extension::permanent_delegate::PermanentDelegate;
+use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use spl_token_2022::extension::transfer_hook::TransferHook;
+use spl_token_2022::state::Account as TokenAccount;
+use spl_token_2022::state::AccountState;
+use spl_token_metadata_interface::state::TokenMetadata;
+use spl_type_length_value::state::TlvStateBorrowed;
+
+/// Serialize/deserialize a `Pubkey` as its base58 string, matching the
+/// `jsonParsed` convention, instead of the derived `[u8; 32]` array.
+mod pubkey_base58 {
+    use serde::{ Deserialize, Deserializer, Serializer };
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&pubkey.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Pubkey::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Same as `pubkey_base58`, but for an `Option<Pubkey>`.
+mod pubkey_base58_option {
+    use serde::{ Deserialize, Deserializer, Serializer };
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(pubkey: &Option<Pubkey>, serializer: S) -> Result<S::Ok, S::Error> {
+        match pubkey {
+            Some(pubkey) => serializer.serialize_some(&pubkey.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Pubkey>, D::Error> {
+        let s: Option<String> = Option::deserialize(deserializer)?;
+        s.map(|s| Pubkey::from_str(&s).map_err(serde::de::Error::custom)).transpose()
+    }
+}
+
+/// Which SPL token program a mint account is owned by.
+///
+/// The classic SPL Token program and Token-2022 share the same base `Mint`
+/// layout, but only Token-2022 mints can carry TLV extensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenProgram {
+    Legacy,
+    Token2022,
+}
+
+impl TokenProgram {
+    pub fn program_id(self) -> Pubkey {
+        match self {
+            TokenProgram::Legacy => spl_token::id(),
+            TokenProgram::Token2022 => spl_token_2022::id(),
+        }
+    }
+}
+
+/// Byte offset at which a Token-2022 mint's first TLV extension entry (and
+/// so its `ExtensionType` discriminator) begins. The base `Mint` layout only
+/// uses the first 82 bytes; the one-byte `AccountType` discriminator sits at
+/// offset 165 so that both `Mint` and `Account` TLV data line up at the same
+/// place, and the TLV entries start right after it, at 166.
+const MINT_TLV_START_OFFSET: usize = 166;
+
+/// Fetch all mint accounts for the given token program(s) on the network,
+/// each tagged with the program that owns it. When `extension` is set, only
+/// mints carrying that Token-2022 extension are returned, filtered
+/// server-side; otherwise every initialized mint is returned.
+pub async fn fetch_mint_accounts(
+    rpc_client: &RpcClient,
+    programs: &[TokenProgram],
+    extension: Option<ExtensionType>
+) -> anyhow::Result<Vec<(Pubkey, Account, TokenProgram)>> {
+    // Issue one `get_program_accounts_with_config` call per requested program,
+    // concurrently, then flatten the results together.
+    let futures = programs
+        .iter()
+        .map(|program| fetch_mints_for_program(rpc_client, *program, extension));
+    let results = join_all(futures).await;
+
+    let mut accounts = Vec::new();
+    for result in results {
+        accounts.extend(result?);
+    }
+
+    Ok(accounts)
+}
+
+/// Fetch all mint accounts owned by a single token program, optionally
+/// narrowed server-side to those carrying a specific Token-2022 extension.
+async fn fetch_mints_for_program(
+    rpc_client: &RpcClient,
+    program: TokenProgram,
+    extension: Option<ExtensionType>
+) -> anyhow::Result<Vec<(Pubkey, Account, TokenProgram)>> {
+    // Classic SPL Token mints carry no TLV data, so they can never match an
+    // extension filter — skip scanning that program entirely rather than
+    // pay for a scan that's guaranteed to return nothing.
+    if extension.is_some() && program != TokenProgram::Token2022 {
+        return Ok(Vec::new());
+    }
 
-/// Fetch all Token-2022 mint accounts on the network.
-/// These are accounts owned by the Token-2022 program ID and represent token mints.
-pub async fn fetch_all_token2022_mints(
-    rpc_client: &RpcClient
-) -> anyhow::Result<Vec<(Pubkey, Account)>> {
     // Use a memcmp filter at offset 45 to match the `is_initialized` byte.
-    // In Token-2022 mint accounts, `is_initialized` is located at byte offset 45
-    // (due to padding after the 33-byte COption<Pubkey> mint_authority, padded to 36).
+    // Both the classic SPL Token and Token-2022 `Mint` share this base layout:
+    // `is_initialized` is located at byte offset 45 (due to padding after the
+    // 33-byte COption<Pubkey> mint_authority, padded to 36).
     //
     // We filter for accounts where this byte is 1, indicating an initialized mint.
     // This may still include false positives (e.g., token accounts that coincidentally
     // have 1 at byte 45), but those will fail due to deserialization as `Mint` or by attempting
     // to retrieve account data for the pda, so they’ll be ignored.
-    let is_initialize_filter: Option<Vec<RpcFilterType>> = Some(
-        vec![RpcFilterType::Memcmp(Memcmp::new(45, MemcmpEncodedBytes::Bytes(vec![1])))]
-    );
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new(45, MemcmpEncodedBytes::Bytes(vec![1])))];
+
+    if let Some(ext_type) = extension {
+        // NOTE: this only matches mints whose *first* TLV entry is the
+        // requested extension. It's a fixed single-offset memcmp, so a mint
+        // with e.g. `MetadataPointer` before `TransferHook` won't match a
+        // `--extension transfer-hook` filter even though it has the
+        // extension — this narrows the scan, it does not guarantee
+        // completeness. Callers that need a complete answer should treat
+        // this as a fast first pass and verify against an unfiltered scan
+        // when they can't tolerate missed mints.
+        let discriminant = u16::from(ext_type);
+        filters.push(
+            RpcFilterType::Memcmp(
+                Memcmp::new(MINT_TLV_START_OFFSET, MemcmpEncodedBytes::Bytes(discriminant.to_le_bytes().to_vec()))
+            )
+        );
+    }
 
     // Configure how to fetch accounts — we want base64-encoded data and confirmed commitment level.
     let config = RpcProgramAccountsConfig {
-        filters: is_initialize_filter,
+        filters: Some(filters),
         account_config: RpcAccountInfoConfig {
             encoding: Some(UiAccountEncoding::Base64),
             commitment: Some(CommitmentConfig::confirmed()),
@@ -40,10 +169,147 @@ pub async fn fetch_all_token2022_mints(
         sort_results: Some(true),
     };
 
-    let program_id = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+    // Fetch all accounts owned by the requested program
+    let accounts = rpc_client.get_program_accounts_with_config(
+        &program.program_id(),
+        config
+    ).await?;
+
+    // The memcmp above only narrows the scan; re-check client-side that each
+    // candidate genuinely carries the requested extension before returning
+    // it, rather than presenting the server-filtered set as authoritative.
+    let accounts: Vec<(Pubkey, Account)> = match extension {
+        Some(ext_type) =>
+            accounts
+                .into_iter()
+                .filter(|(_, account)| mint_has_extension(account, ext_type))
+                .collect(),
+        None => accounts,
+    };
+
+    Ok(
+        accounts
+            .into_iter()
+            .map(|(pubkey, account)| (pubkey, account, program))
+            .collect()
+    )
+}
+
+/// Whether a mint account's TLV data genuinely contains `ext_type`, used to
+/// confirm matches from the single-offset `--extension` memcmp filter above.
+fn mint_has_extension(account: &Account, ext_type: ExtensionType) -> bool {
+    let Ok(state) = StateWithExtensions::<Mint>::unpack(&account.data) else {
+        return false;
+    };
+    state.get_extension_types().unwrap_or_default().contains(&ext_type)
+}
+
+/// An on-disk, zstd-compressed snapshot of `fetch_mint_accounts`' output,
+/// keyed by the RPC endpoint, program id(s), and commitment level it was
+/// fetched with, and stamped with both the slot and wall-clock time of the
+/// scan.
+#[derive(Serialize, Deserialize)]
+struct MintAccountCache {
+    rpc_url: String,
+    program_ids: Vec<Pubkey>,
+    commitment: String,
+    cached_at_slot: u64,
+    cached_at_unix_secs: u64,
+    accounts: Vec<(Pubkey, Account, TokenProgram)>,
+}
+
+/// Load a cached set of mint accounts from `path` if it exists, matches the
+/// requested `rpc_url`, `programs`, and `commitment`, and is no older than
+/// `max_age`.
+pub fn load_cached_mint_accounts(
+    path: &Path,
+    rpc_url: &str,
+    programs: &[TokenProgram],
+    commitment: CommitmentConfig,
+    max_age: Duration
+) -> Option<Vec<(Pubkey, Account, TokenProgram)>> {
+    let compressed = std::fs::read(path).ok()?;
+    let serialized = zstd::stream::decode_all(&compressed[..]).ok()?;
+    let cache: MintAccountCache = bincode::deserialize(&serialized).ok()?;
 
-    // Fetch all accounts owned by the Token-2022 program
-    let accounts = rpc_client.get_program_accounts_with_config(&program_id, config).await?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    if now.saturating_sub(cache.cached_at_unix_secs) > max_age.as_secs() {
+        return None;
+    }
+
+    if cache.rpc_url != rpc_url {
+        return None;
+    }
+
+    if cache.commitment != format!("{:?}", commitment.commitment) {
+        return None;
+    }
+
+    let mut expected_program_ids: Vec<Pubkey> = programs.iter().map(|p| p.program_id()).collect();
+    expected_program_ids.sort();
+    let mut cached_program_ids = cache.program_ids.clone();
+    cached_program_ids.sort();
+    if expected_program_ids != cached_program_ids {
+        return None;
+    }
+
+    Some(cache.accounts)
+}
+
+/// Write `accounts` to `path` as a zstd-compressed snapshot, keyed by the
+/// `rpc_url`, `programs`, and `commitment` they were fetched with, stamped
+/// with the given scan `slot`.
+pub fn save_cached_mint_accounts(
+    path: &Path,
+    rpc_url: &str,
+    programs: &[TokenProgram],
+    commitment: CommitmentConfig,
+    slot: u64,
+    accounts: &[(Pubkey, Account, TokenProgram)]
+) -> anyhow::Result<()> {
+    let cache = MintAccountCache {
+        rpc_url: rpc_url.to_string(),
+        program_ids: programs.iter().map(|p| p.program_id()).collect(),
+        commitment: format!("{:?}", commitment.commitment),
+        cached_at_slot: slot,
+        cached_at_unix_secs: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        accounts: accounts.to_vec(),
+    };
+
+    let serialized = bincode::serialize(&cache)?;
+    let compressed = zstd::stream::encode_all(&serialized[..], 0)?;
+    std::fs::write(path, compressed)?;
+
+    Ok(())
+}
+
+/// Fetch mint accounts for the given programs, transparently using a local
+/// cache file when it exists, matches the current `rpc_url`, and is fresh,
+/// and refreshing it otherwise.
+pub async fn fetch_mint_accounts_cached(
+    rpc_client: &RpcClient,
+    rpc_url: &str,
+    programs: &[TokenProgram],
+    cache_path: &Path,
+    max_age: Duration
+) -> anyhow::Result<Vec<(Pubkey, Account, TokenProgram)>> {
+    let commitment = CommitmentConfig::confirmed();
+
+    if
+        let Some(accounts) = load_cached_mint_accounts(
+            cache_path,
+            rpc_url,
+            programs,
+            commitment,
+            max_age
+        )
+    {
+        return Ok(accounts);
+    }
+
+    let accounts = fetch_mint_accounts(rpc_client, programs, None).await?;
+    let slot = rpc_client.get_slot().await?;
+    save_cached_mint_accounts(cache_path, rpc_url, programs, commitment, slot, &accounts)?;
 
     Ok(accounts)
 }
@@ -54,6 +320,451 @@ pub fn derive_metadata_pda(mint: &Pubkey) -> Pubkey {
     Metadata::find_pda(mint).0
 }
 
+/// A `UiTokenAmount`-style view of a raw token amount, mirroring the
+/// `jsonParsed` convention used by Solana's account-decoder: the raw amount
+/// as a decimal string, the mint's decimals, and both a lossy `f64` and a
+/// lossless string form of the UI amount.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiTokenAmount {
+    pub amount: String,
+    pub decimals: u8,
+    pub ui_amount: f64,
+    pub ui_amount_string: String,
+}
+
+impl UiTokenAmount {
+    fn from_raw(raw_amount: u64, decimals: u8) -> Self {
+        Self {
+            amount: raw_amount.to_string(),
+            decimals,
+            ui_amount: amount_to_ui_amount(raw_amount, decimals),
+            ui_amount_string: amount_to_ui_amount_string_trimmed(raw_amount, decimals),
+        }
+    }
+}
+
+/// A Token-2022 extension decoded into its actual on-chain parameters, rather
+/// than just its bare `ExtensionType` name.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DecodedExtension {
+    TransferFeeConfig {
+        #[serde(with = "pubkey_base58_option")]
+        transfer_fee_config_authority: Option<Pubkey>,
+        #[serde(with = "pubkey_base58_option")]
+        withdraw_withheld_authority: Option<Pubkey>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+        older_transfer_fee_basis_points: u16,
+        older_maximum_fee: u64,
+    },
+    InterestBearingConfig {
+        #[serde(with = "pubkey_base58_option")]
+        rate_authority: Option<Pubkey>,
+        initialization_timestamp: i64,
+        pre_update_average_rate: i16,
+        last_update_timestamp: i64,
+        current_rate: i16,
+    },
+    MintCloseAuthority {
+        #[serde(with = "pubkey_base58_option")]
+        close_authority: Option<Pubkey>,
+    },
+    PermanentDelegate {
+        #[serde(with = "pubkey_base58_option")]
+        delegate: Option<Pubkey>,
+    },
+    TransferHook {
+        #[serde(with = "pubkey_base58_option")]
+        authority: Option<Pubkey>,
+        #[serde(with = "pubkey_base58_option")]
+        program_id: Option<Pubkey>,
+    },
+    MetadataPointer {
+        #[serde(with = "pubkey_base58_option")]
+        authority: Option<Pubkey>,
+        #[serde(with = "pubkey_base58_option")]
+        metadata_address: Option<Pubkey>,
+    },
+    DefaultAccountState {
+        state: String,
+    },
+    /// An extension whose contents aren't decoded: either a type not handled
+    /// here, or a handled type whose TLV data couldn't be read.
+    Other {
+        name: String,
+    },
+}
+
+/// Decode a single extension out of a mint's TLV data, falling back to just
+/// its name when the extension isn't one we know how to decode (or is
+/// present in the type list but missing/malformed in the TLV data).
+fn decode_extension(state: &StateWithExtensions<Mint>, ext_type: ExtensionType) -> DecodedExtension {
+    let fallback = || DecodedExtension::Other { name: format!("{:?}", ext_type) };
+
+    match ext_type {
+        ExtensionType::TransferFeeConfig => {
+            match state.get_extension::<TransferFeeConfig>() {
+                Ok(ext) =>
+                    DecodedExtension::TransferFeeConfig {
+                        transfer_fee_config_authority: Option::<Pubkey>::from(
+                            ext.transfer_fee_config_authority
+                        ),
+                        withdraw_withheld_authority: Option::<Pubkey>::from(
+                            ext.withdraw_withheld_authority
+                        ),
+                        transfer_fee_basis_points: u16::from(
+                            ext.newer_transfer_fee.transfer_fee_basis_points
+                        ),
+                        maximum_fee: u64::from(ext.newer_transfer_fee.maximum_fee),
+                        older_transfer_fee_basis_points: u16::from(
+                            ext.older_transfer_fee.transfer_fee_basis_points
+                        ),
+                        older_maximum_fee: u64::from(ext.older_transfer_fee.maximum_fee),
+                    },
+                Err(_) => fallback(),
+            }
+        }
+        ExtensionType::InterestBearingConfig => {
+            match state.get_extension::<InterestBearingConfig>() {
+                Ok(ext) =>
+                    DecodedExtension::InterestBearingConfig {
+                        rate_authority: Option::<Pubkey>::from(ext.rate_authority),
+                        initialization_timestamp: i64::from(ext.initialization_timestamp),
+                        pre_update_average_rate: i16::from(ext.pre_update_average_rate),
+                        last_update_timestamp: i64::from(ext.last_update_timestamp),
+                        current_rate: i16::from(ext.current_rate),
+                    },
+                Err(_) => fallback(),
+            }
+        }
+        ExtensionType::MintCloseAuthority => {
+            match state.get_extension::<MintCloseAuthority>() {
+                Ok(ext) =>
+                    DecodedExtension::MintCloseAuthority {
+                        close_authority: Option::<Pubkey>::from(ext.close_authority),
+                    },
+                Err(_) => fallback(),
+            }
+        }
+        ExtensionType::PermanentDelegate => {
+            match state.get_extension::<PermanentDelegate>() {
+                Ok(ext) =>
+                    DecodedExtension::PermanentDelegate {
+                        delegate: Option::<Pubkey>::from(ext.delegate),
+                    },
+                Err(_) => fallback(),
+            }
+        }
+        ExtensionType::TransferHook => {
+            match state.get_extension::<TransferHook>() {
+                Ok(ext) =>
+                    DecodedExtension::TransferHook {
+                        authority: Option::<Pubkey>::from(ext.authority),
+                        program_id: Option::<Pubkey>::from(ext.program_id),
+                    },
+                Err(_) => fallback(),
+            }
+        }
+        ExtensionType::MetadataPointer => {
+            match state.get_extension::<MetadataPointer>() {
+                Ok(ext) =>
+                    DecodedExtension::MetadataPointer {
+                        authority: Option::<Pubkey>::from(ext.authority),
+                        metadata_address: Option::<Pubkey>::from(ext.metadata_address),
+                    },
+                Err(_) => fallback(),
+            }
+        }
+        ExtensionType::DefaultAccountState => {
+            match state.get_extension::<DefaultAccountState>() {
+                Ok(ext) => {
+                    let state = AccountState::try_from(ext.state).unwrap_or(
+                        AccountState::Uninitialized
+                    );
+                    DecodedExtension::DefaultAccountState { state: format!("{:?}", state) }
+                }
+                Err(_) => fallback(),
+            }
+        }
+        _ => fallback(),
+    }
+}
+
+/// A structured, serializable record for a single mint, suitable for piping
+/// into other tools via `--format json`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MintRecord {
+    #[serde(with = "pubkey_base58")]
+    pub mint: Pubkey,
+    pub program: TokenProgram,
+    #[serde(with = "pubkey_base58")]
+    pub metadata_pda: Pubkey,
+    #[serde(flatten)]
+    pub token_amount: UiTokenAmount,
+    pub extensions: Vec<DecodedExtension>,
+}
+
+/// Unpack a mint's base state and bundle its supply, decimals, and decoded
+/// extensions into a `MintRecord`. Extension parsing is skipped for classic
+/// SPL Token mints, which have no TLV data.
+fn mint_record(
+    mint_pubkey: &Pubkey,
+    account: &Account,
+    program: TokenProgram,
+    metadata_pda: Pubkey
+) -> Option<MintRecord> {
+    let (supply, decimals, extensions) = match program {
+        TokenProgram::Legacy => {
+            let mint = spl_token::state::Mint::unpack(&account.data).ok()?;
+            (mint.supply, mint.decimals, Vec::new())
+        }
+        TokenProgram::Token2022 => {
+            let state = StateWithExtensions::<Mint>::unpack(&account.data).ok()?;
+            let extensions = state
+                .get_extension_types()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|ext| decode_extension(&state, ext))
+                .collect();
+            (state.base.supply, state.base.decimals, extensions)
+        }
+    };
+
+    Some(MintRecord {
+        mint: *mint_pubkey,
+        program,
+        metadata_pda,
+        token_amount: UiTokenAmount::from_raw(supply, decimals),
+        extensions,
+    })
+}
+
+/// Where a mint's `TokenMetadata` was resolved from.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum MetadataSource {
+    /// The `MetadataPointer` extension points at the mint itself, so the
+    /// `TokenMetadata` extension is embedded directly in the mint's own TLV data.
+    InlineTokenMetadataExtension,
+    /// The `MetadataPointer` extension points at a separate account holding
+    /// the `TokenMetadata` in the same TLV layout.
+    ExternalTokenMetadataAccount {
+        #[serde(with = "pubkey_base58")]
+        address: Pubkey,
+    },
+    /// Metadata was read from an account in Metaplex's `Metadata` layout: the
+    /// mint's Metaplex PDA when it has no `MetadataPointer` extension (or one
+    /// with no metadata address), or the pointer's target account when that
+    /// account holds Metaplex-layout data rather than TLV `TokenMetadata`.
+    MetaplexPda,
+}
+
+/// A mint's resolved metadata, regardless of which on-chain source it came from.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedMetadata {
+    pub source: MetadataSource,
+    #[serde(with = "pubkey_base58_option")]
+    pub update_authority: Option<Pubkey>,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub additional_metadata: Vec<(String, String)>,
+}
+
+impl ResolvedMetadata {
+    fn from_token_metadata(source: MetadataSource, token_metadata: &TokenMetadata) -> Self {
+        Self {
+            source,
+            update_authority: Option::<Pubkey>::from(token_metadata.update_authority),
+            name: token_metadata.name.clone(),
+            symbol: token_metadata.symbol.clone(),
+            uri: token_metadata.uri.clone(),
+            additional_metadata: token_metadata.additional_metadata.clone(),
+        }
+    }
+
+    fn from_metaplex(metadata: Metadata) -> Self {
+        Self {
+            source: MetadataSource::MetaplexPda,
+            update_authority: Some(metadata.update_authority),
+            name: metadata.name.trim_end_matches('\0').to_string(),
+            symbol: metadata.symbol.trim_end_matches('\0').to_string(),
+            uri: metadata.uri.trim_end_matches('\0').to_string(),
+            additional_metadata: Vec::new(),
+        }
+    }
+}
+
+/// A mint together with its resolved `TokenMetadata`, carrying the same
+/// `UiTokenAmount` supply and derived metadata PDA as the original
+/// `--format json` contract.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataRecord {
+    #[serde(with = "pubkey_base58")]
+    pub mint: Pubkey,
+    pub program: TokenProgram,
+    #[serde(with = "pubkey_base58")]
+    pub metadata_pda: Pubkey,
+    #[serde(flatten)]
+    pub token_amount: UiTokenAmount,
+    pub extensions: Vec<DecodedExtension>,
+    pub metadata: ResolvedMetadata,
+}
+
+/// Resolve the `TokenMetadata` for a batch of mints. Metadata derivation
+/// applies to both classic SPL Token and Token-2022 mints.
+///
+/// For each mint, the `MetadataPointer` extension is consulted first: if it
+/// points at the mint itself, the `TokenMetadata` extension is decoded
+/// directly out of the mint's own TLV data; if it points elsewhere, that
+/// account is fetched and decoded as TLV-encoded `TokenMetadata`, or failing
+/// that, in the Metaplex `Metadata` layout. Mints with no `MetadataPointer`
+/// extension, or one with no metadata address, fall back to the Metaplex PDA.
+pub async fn resolve_mint_metadata(
+    rpc_client: &RpcClient,
+    mint_accounts: &[(Pubkey, Account, TokenProgram)]
+) -> anyhow::Result<Vec<(Pubkey, TokenProgram, Option<ResolvedMetadata>)>> {
+    let mut resolved: Vec<(Pubkey, TokenProgram, Option<ResolvedMetadata>)> = mint_accounts
+        .iter()
+        .map(|(mint_pubkey, _, program)| (*mint_pubkey, *program, None))
+        .collect();
+
+    // Mints whose metadata lives in a separate account we still need to fetch:
+    // either the `MetadataPointer` target, or the Metaplex PDA fallback.
+    let mut pending_pubkeys: Vec<Pubkey> = Vec::new();
+    let mut pending_indices: Vec<usize> = Vec::new();
+
+    for (index, (mint_pubkey, account, _)) in mint_accounts.iter().enumerate() {
+        let Ok(state) = StateWithExtensions::<Mint>::unpack(&account.data) else {
+            continue;
+        };
+
+        if let Ok(pointer) = state.get_extension::<MetadataPointer>() {
+            if let Some(metadata_address) = Option::<Pubkey>::from(pointer.metadata_address) {
+                if metadata_address == *mint_pubkey {
+                    if let Ok(token_metadata) = state.get_variable_len_extension::<TokenMetadata>() {
+                        resolved[index].2 = Some(
+                            ResolvedMetadata::from_token_metadata(
+                                MetadataSource::InlineTokenMetadataExtension,
+                                &token_metadata
+                            )
+                        );
+                    }
+                    continue;
+                }
+
+                pending_pubkeys.push(metadata_address);
+                pending_indices.push(index);
+                continue;
+            }
+        }
+
+        pending_pubkeys.push(derive_metadata_pda(mint_pubkey));
+        pending_indices.push(index);
+    }
+
+    if pending_pubkeys.is_empty() {
+        return Ok(resolved);
+    }
+
+    let pending_accounts = fetch_metadata_accounts(rpc_client, &pending_pubkeys).await?;
+
+    for ((index, address), maybe_account) in pending_indices
+        .into_iter()
+        .zip(pending_pubkeys.into_iter())
+        .zip(pending_accounts.into_iter()) {
+        let Some(account) = maybe_account else {
+            continue;
+        };
+        if account.lamports == 0 || account.data.is_empty() {
+            continue;
+        }
+
+        // Try the TLV-encoded `TokenMetadata` layout first (the external
+        // pointer-target case), then fall back to the Metaplex account layout.
+        if let Ok(tlv_state) = TlvStateBorrowed::unpack(&account.data) {
+            if let Ok(token_metadata) = tlv_state.get_first_variable_len_value::<TokenMetadata>() {
+                resolved[index].2 = Some(
+                    ResolvedMetadata::from_token_metadata(
+                        MetadataSource::ExternalTokenMetadataAccount { address },
+                        &token_metadata
+                    )
+                );
+                continue;
+            }
+        }
+
+        if account.owner != solana_sdk::system_program::id() {
+            if let Ok(metadata) = Metadata::safe_deserialize(&account.data) {
+                resolved[index].2 = Some(ResolvedMetadata::from_metaplex(metadata));
+            }
+        }
+    }
+
+    Ok(resolved)
+}
+
+/// Build the structured records for the `GetTokensWithMetadataAccount`
+/// command out of `resolve_mint_metadata`'s output, pairing each resolved
+/// mint with its supply and derived metadata PDA. Mints whose metadata
+/// couldn't be resolved are dropped.
+pub fn build_metadata_records(
+    mint_accounts: &[(Pubkey, Account, TokenProgram)],
+    resolved: Vec<(Pubkey, TokenProgram, Option<ResolvedMetadata>)>
+) -> Vec<MetadataRecord> {
+    resolved
+        .into_iter()
+        .zip(mint_accounts.iter())
+        .filter_map(|((mint, program, metadata), (_, account, _))| {
+            let metadata = metadata?;
+            let state = StateWithExtensions::<Mint>::unpack(&account.data).ok()?;
+
+            let extensions = match program {
+                TokenProgram::Legacy => Vec::new(),
+                TokenProgram::Token2022 =>
+                    state
+                        .get_extension_types()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|ext| decode_extension(&state, ext))
+                        .collect(),
+            };
+
+            Some(MetadataRecord {
+                mint,
+                program,
+                metadata_pda: derive_metadata_pda(&mint),
+                token_amount: UiTokenAmount::from_raw(state.base.supply, state.base.decimals),
+                extensions,
+                metadata,
+            })
+        })
+        .collect()
+}
+
+/// Build the structured records for the `GetTokensWithExtensions` command from
+/// the mints that `filter_mints_with_extensions` identified as carrying one or
+/// more extensions.
+pub fn build_extension_records(
+    accounts: &[(Pubkey, Account, TokenProgram)],
+    mints_with_exts: &[(Pubkey, Vec<DecodedExtension>)]
+) -> Vec<MintRecord> {
+    mints_with_exts
+        .iter()
+        .filter_map(|(mint_pubkey, _)| {
+            let (_, account, program) = accounts
+                .iter()
+                .find(|(pubkey, _, _)| pubkey == mint_pubkey)?;
+            mint_record(mint_pubkey, account, *program, derive_metadata_pda(mint_pubkey))
+        })
+        .collect()
+}
+
 /// Given a list of metadata PDAs, fetch the account data for each in parallel.
 /// This is useful to check which PDAs actually exist and contain valid metadata.
 pub async fn fetch_metadata_accounts(
@@ -92,47 +803,50 @@ pub async fn fetch_metadata_accounts(
     Ok(all_accounts)
 }
 
-/// For each metadata account that exists and deserializes successfully,
-/// print the mint address and its associated metadata PDA.
-pub fn print_metadata_results(metadata_pubkeys: &[Pubkey], metadata_accounts: &[Option<Account>]) {
-    for (pda, maybe_account) in metadata_pubkeys.iter().zip(metadata_accounts.iter()) {
-        if let Some(account) = maybe_account {
-            //check if this account is just a cached account. So a derived account that has been closed but still leaves in the ledger
-            if
-                account.lamports == 0 ||
-                account.data.is_empty() ||
-                account.owner == solana_sdk::system_program::id()
-            {
-                println!("Skipping dead metadata account: {}", pda);
-                continue;
-            }
-
-            if let Ok(metadata) = Metadata::safe_deserialize(&account.data) {
-                println!("Mint: {}\nMetadata Account: {}\n", metadata.mint, pda);
-            }
-        }
+/// Print each mint's resolved metadata, and which on-chain source it came from.
+pub fn print_metadata_results(records: &[MetadataRecord]) {
+    for record in records {
+        println!(
+            "Mint: {}\nMetadata PDA: {}\nSupply: {}\nExtensions: {}\nSource: {:?}\nName: {}\nSymbol: {}\nURI: {}\n",
+            record.mint,
+            record.metadata_pda,
+            record.token_amount.ui_amount_string,
+            record.extensions.len(),
+            record.metadata.source,
+            record.metadata.name,
+            record.metadata.symbol,
+            record.metadata.uri
+        );
     }
 }
 
-/// Given a list of mint accounts, return those that contain one or more extensions.
-/// Token-2022 supports optional TLV-based extensions on mints and accounts.
-pub fn filter_mints_with_extensions(accounts: &[(Pubkey, Account)]) -> Vec<(Pubkey, Vec<String>)> {
+/// Given a list of mint accounts, return those that contain one or more extensions,
+/// together with each extension fully decoded into its on-chain parameters.
+/// Token-2022 supports optional TLV-based extensions on mints and accounts;
+/// classic SPL Token mints have no TLV data and are skipped.
+pub fn filter_mints_with_extensions(
+    accounts: &[(Pubkey, Account, TokenProgram)]
+) -> Vec<(Pubkey, Vec<DecodedExtension>)> {
     let mut results = Vec::new();
 
     // Attempt to unpack the mint account into a `StateWithExtensions<Mint>` struct,
     // which holds both the base mint and any TLV extension data.
-    for (pubkey, account) in accounts {
+    for (pubkey, account, program) in accounts {
+        if *program != TokenProgram::Token2022 {
+            continue;
+        }
+
         if let Ok(state) = StateWithExtensions::<Mint>::unpack(&account.data) {
             // Extract the types of all token extensions, if any
             let extensions = state.get_extension_types().unwrap_or_default();
 
             if !extensions.is_empty() {
-                // Format the extension types as strings for display
-                let names: Vec<String> = extensions
-                    .iter()
-                    .map(|ext| format!("{:?}", ext))
+                // Decode each extension type into its actual on-chain parameters
+                let decoded: Vec<DecodedExtension> = extensions
+                    .into_iter()
+                    .map(|ext| decode_extension(&state, ext))
                     .collect();
-                results.push((*pubkey, names));
+                results.push((*pubkey, decoded));
             }
         }
     }
@@ -140,14 +854,121 @@ pub fn filter_mints_with_extensions(accounts: &[(Pubkey, Account)]) -> Vec<(Pubk
     results
 }
 
-/// Print the mint addresses and their associated token extension names.
-pub fn print_mints_with_extensions(mints_with_exts: &[(Pubkey, Vec<String>)]) {
-    for (mint, extensions) in mints_with_exts {
-        println!("Mint: {}", mint);
+/// Print the mint addresses and their decoded token extensions.
+pub fn print_mints_with_extensions(records: &[MintRecord]) {
+    for record in records {
+        println!("Mint: {}", record.mint);
         println!("Extensions:");
-        for ext in extensions {
-            println!("  - {}", ext);
+        for ext in &record.extensions {
+            println!("  - {:?}", ext);
         }
         println!();
     }
 }
+
+/// The number of largest token accounts returned by `fetch_token_info`,
+/// matching the default page size of Solana's `getTokenLargestAccounts` RPC.
+const LARGEST_ACCOUNTS_LIMIT: usize = 20;
+
+/// A single token account holding a balance of some mint, with its balance
+/// expressed the same `UiTokenAmount` way as a mint's total supply.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenHolder {
+    #[serde(with = "pubkey_base58")]
+    pub address: Pubkey,
+    #[serde(flatten)]
+    pub amount: UiTokenAmount,
+}
+
+/// Total supply and largest holders for a single Token-2022 mint.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenInfo {
+    #[serde(with = "pubkey_base58")]
+    pub mint: Pubkey,
+    #[serde(flatten)]
+    pub supply: UiTokenAmount,
+    pub largest_accounts: Vec<TokenHolder>,
+}
+
+/// Fetch a mint's total supply and its largest token-account holders.
+///
+/// Supply is read directly from the mint's base state. Holders are found by
+/// scanning the Token-2022 program's accounts with a `Memcmp` on the mint
+/// field at offset 0 (no `dataSize` filter, so extended token accounts are
+/// included), then decoding and sorting balances client-side, since
+/// `getTokenLargestAccounts` isn't available on every RPC provider.
+pub async fn fetch_token_info(rpc_client: &RpcClient, mint: &Pubkey) -> anyhow::Result<TokenInfo> {
+    let mint_account = rpc_client.get_account(mint).await?;
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+    let supply = UiTokenAmount::from_raw(mint_state.base.supply, mint_state.base.decimals);
+
+    let mut holders = fetch_largest_token_accounts(rpc_client, mint).await?;
+    holders.sort_by(|a, b| b.1.cmp(&a.1));
+    holders.truncate(LARGEST_ACCOUNTS_LIMIT);
+
+    let largest_accounts = holders
+        .into_iter()
+        .map(|(address, raw_amount)| TokenHolder {
+            address,
+            amount: UiTokenAmount::from_raw(raw_amount, mint_state.base.decimals),
+        })
+        .collect();
+
+    Ok(TokenInfo {
+        mint: *mint,
+        supply,
+        largest_accounts,
+    })
+}
+
+/// Fetch every token account for `mint` on the Token-2022 program,
+/// including those carrying TLV extensions (e.g. `TransferFeeAmount`,
+/// `ImmutableOwner`), paired with its raw `amount`.
+async fn fetch_largest_token_accounts(
+    rpc_client: &RpcClient,
+    mint: &Pubkey
+) -> anyhow::Result<Vec<(Pubkey, u64)>> {
+    // Token accounts store their owning mint as the first 32 bytes, so a
+    // `Memcmp` of the mint pubkey at offset 0 selects exactly this mint's
+    // holders. We deliberately don't constrain `dataSize`: extensions like
+    // `TransferFeeAmount` (forced on every holder of a transfer-fee mint)
+    // or `ImmutableOwner` push accounts past the unextended 165-byte layout.
+    let filters = Some(
+        vec![RpcFilterType::Memcmp(Memcmp::new(0, MemcmpEncodedBytes::Bytes(mint.to_bytes().to_vec())))]
+    );
+
+    let config = RpcProgramAccountsConfig {
+        filters,
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..RpcAccountInfoConfig::default()
+        },
+        with_context: None,
+        sort_results: Some(true),
+    };
+
+    let accounts = rpc_client.get_program_accounts_with_config(&spl_token_2022::id(), config).await?;
+
+    Ok(
+        accounts
+            .into_iter()
+            .filter_map(|(pubkey, account)| {
+                let state = StateWithExtensions::<TokenAccount>::unpack(&account.data).ok()?;
+                Some((pubkey, state.base.amount))
+            })
+            .collect()
+    )
+}
+
+/// Print a mint's total supply and its largest holders.
+pub fn print_token_info(info: &TokenInfo) {
+    println!("Mint: {}", info.mint);
+    println!("Supply: {} ({})", info.supply.ui_amount_string, info.supply.amount);
+    println!("Largest accounts:");
+    for holder in &info.largest_accounts {
+        println!("  {} — {}", holder.address, holder.amount.ui_amount_string);
+    }
+}